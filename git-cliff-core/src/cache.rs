@@ -0,0 +1,159 @@
+//! Binary on-disk cache for a previously computed releases graph.
+//!
+//! Reparsing and recomputing the full release graph on every invocation is
+//! expensive for large histories. [`Releases::save_cache`] serializes the
+//! computed releases keyed by the HEAD commit id they were computed at, and
+//! [`Releases::load_cache`] loads them back as long as HEAD has not moved.
+//!
+//! This module does not itself update a stale cache incrementally --
+//! [`load_or_compute`] does not know how to diff two commit ranges, so on
+//! its own it either returns an exact cache hit or recomputes via `compute`. [`load_stale`]
+//! exposes the previously cached releases and head regardless of whether
+//! HEAD has moved, so a caller that *does* know how to walk "commits since
+//! the cached tip" (e.g. the `git-cliff` binary, once it grows that logic)
+//! can pass them into [`load_or_compute`]'s `compute` closure and only
+//! process the new commits instead of recomputing the full graph.
+//!
+//! [`Releases::save_cache`]: crate::release::Releases::save_cache
+//! [`Releases::load_cache`]: crate::release::Releases::load_cache
+
+use crate::error::Result;
+use crate::release::{
+	Release,
+	Releases,
+};
+use serde::{
+	Deserialize,
+	Serialize,
+};
+use std::path::Path;
+
+/// Default file name for the on-disk release cache.
+pub const CACHE_FILE_NAME: &str = "releases.cache";
+
+/// On-disk representation of the cache, keyed by the HEAD commit id it was
+/// generated at.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CacheFile {
+	/// HEAD commit id the cache was generated at.
+	pub(crate) head:     String,
+	/// Cached releases, newest first, as computed at `head`.
+	pub(crate) releases: Vec<Release<'static>>,
+}
+
+/// A cache found on disk, regardless of whether it is still fresh for the
+/// current HEAD.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheLookup {
+	/// HEAD commit id the cache was generated at.
+	pub head:     String,
+	/// Releases as computed at `head`.
+	pub releases: Vec<Release<'static>>,
+}
+
+/// Loads whatever is cached at `path`, if anything, without comparing it
+/// against a current HEAD.
+///
+/// Unlike [`Releases::load_cache`], this returns the stale entry too, so a
+/// caller can diff its `head` against the current one and only reprocess
+/// the commits in between.
+pub fn load_stale(path: &Path) -> Result<Option<CacheLookup>> {
+	match Releases::load_cache_file(path)? {
+		Some(file) => Ok(Some(CacheLookup {
+			head:     file.head,
+			releases: file.releases,
+		})),
+		None => Ok(None),
+	}
+}
+
+/// Loads the releases cached at `path` if it is still fresh for `head`, or
+/// runs `compute` and persists its result to `path` for the next run.
+///
+/// `compute` is handed the stale cache entry, if any, via [`load_stale`], so
+/// it can recompute only the commits newer than the cached tip instead of
+/// the full release graph. `compute` is free to ignore it and recompute
+/// everything, e.g. when no cache exists yet.
+pub fn load_or_compute(
+	path: &Path,
+	head: &str,
+	compute: impl FnOnce(Option<CacheLookup>) -> Result<Vec<Release<'static>>>,
+) -> Result<Vec<Release<'static>>> {
+	let stale = load_stale(path)?;
+	if let Some(cached) = &stale {
+		if cached.head == head {
+			return Ok(cached.releases.clone());
+		}
+	}
+	let releases = compute(stale)?;
+	Releases {
+		releases: &releases,
+	}
+	.save_cache(path, head)?;
+	Ok(releases)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn computes_once_then_reuses_cache() -> Result<()> {
+		let path = std::env::temp_dir().join("git-cliff-load-or-compute-test.cache");
+		let _ = std::fs::remove_file(&path);
+
+		let mut compute_calls = 0;
+		let releases = load_or_compute(&path, "deadbeef", |stale| {
+			compute_calls += 1;
+			assert!(stale.is_none(), "no cache exists yet");
+			Ok(vec![Release {
+				version: Some(String::from("1.0.0")),
+				..Default::default()
+			}])
+		})?;
+		assert_eq!(1, compute_calls);
+
+		let cached = load_or_compute(&path, "deadbeef", |_stale| {
+			compute_calls += 1;
+			Ok(Vec::new())
+		})?;
+		assert_eq!(1, compute_calls, "second call should hit the cache");
+		assert_eq!(releases, cached);
+
+		std::fs::remove_file(&path)?;
+		Ok(())
+	}
+
+	#[test]
+	fn stale_cache_is_handed_to_compute_for_incremental_update() -> Result<()> {
+		let path =
+			std::env::temp_dir().join("git-cliff-load-or-compute-stale-test.cache");
+		let _ = std::fs::remove_file(&path);
+
+		load_or_compute(&path, "deadbeef", |_stale| {
+			Ok(vec![Release {
+				version: Some(String::from("1.0.0")),
+				..Default::default()
+			}])
+		})?;
+
+		let mut seen_stale = None;
+		let updated = load_or_compute(&path, "f00dcafe", |stale| {
+			seen_stale = stale.clone();
+			let mut releases = stale.map(|cache| cache.releases).unwrap_or_default();
+			releases.push(Release {
+				version: Some(String::from("1.1.0")),
+				..Default::default()
+			});
+			Ok(releases)
+		})?;
+
+		let seen_stale = seen_stale.expect("stale cache was passed to compute");
+		assert_eq!("deadbeef", seen_stale.head);
+		assert_eq!(1, seen_stale.releases.len());
+		assert_eq!(2, updated.len());
+
+		std::fs::remove_file(&path)?;
+		Ok(())
+	}
+}
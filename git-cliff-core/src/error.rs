@@ -0,0 +1,31 @@
+use thiserror::Error;
+
+/// Result type of the core library.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Error variants produced by the core library.
+#[derive(Debug, Error)]
+pub enum Error {
+	/// Error variant for I/O operations.
+	#[error("IO error: `{0}`")]
+	IoError(#[from] std::io::Error),
+	/// Error variant for semantic version parsing/bumping.
+	#[error("Cannot parse semver: `{0}`")]
+	SemverError(#[from] semver::Error),
+	/// Error variant for JSON serialization/deserialization.
+	#[error("JSON error: `{0}`")]
+	JsonError(#[from] serde_json::Error),
+	/// Error variant for HTTP requests to a forge.
+	#[error("HTTP request error: `{0}`")]
+	HttpError(#[from] reqwest::Error),
+	/// Error variant for missing/invalid configuration, e.g. an unset
+	/// environment variable.
+	#[error("Configuration error: `{0}`")]
+	ConfigError(String),
+	/// Error variant for the crates.io registry resolver.
+	#[error("Registry error: `{0}`")]
+	RegistryError(String),
+	/// Error variant for the binary releases cache.
+	#[error("Bincode error: `{0}`")]
+	BincodeError(#[from] bincode::Error),
+}
@@ -0,0 +1,282 @@
+//! Publishing computed releases to a forge's REST API.
+//!
+//! This lets `git-cliff` create the actual release object on Gitea, GitHub
+//! or GitLab right after computing it, instead of requiring the output to
+//! be piped into a separate tool. Consumers build a [`PublishConfig`] (see
+//! [`PublishConfig::from_env`]) and call [`PublishClient::publish`], or use
+//! [`publish_release_from_env`] as a single entry point.
+//!
+//! This is a separate, unrelated module from the `[remote.github]` /
+//! `[remote.gitlab]` / `[remote.gitea]` integration that enriches commits
+//! with PR/contributor data for templates -- this one pushes a computed
+//! release *to* a forge, rather than pulling data *from* one.
+//!
+//! This crate only contains `git-cliff-core`, the library; there is no
+//! binary/CLI crate in this tree to wire a `--publish` flag into, so this
+//! module is library-only plumbing. Exposing it as a CLI flag or subcommand
+//! is out of scope here and left to whatever crate ends up housing the
+//! `git-cliff` binary.
+
+use crate::error::Result;
+use crate::release::Release;
+use serde::{
+	Deserialize,
+	Serialize,
+};
+
+/// Environment variable that holds the forge API token.
+pub const PUBLISH_TOKEN_ENV: &str = "GIT_CLIFF_PUBLISH_TOKEN";
+
+/// Forge that a release can be published to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForgeKind {
+	/// Gitea and Gitea-compatible instances (e.g. Codeberg).
+	Gitea,
+	/// GitHub and GitHub Enterprise.
+	GitHub,
+	/// GitLab and self-hosted GitLab instances.
+	GitLab,
+}
+
+impl ForgeKind {
+	/// Returns the path of the "create a release" endpoint for `owner/repo`.
+	fn releases_path(&self, owner: &str, repo: &str) -> String {
+		match self {
+			ForgeKind::Gitea => format!("/api/v1/repos/{owner}/{repo}/releases"),
+			ForgeKind::GitHub => format!("/repos/{owner}/{repo}/releases"),
+			ForgeKind::GitLab => {
+				format!("/api/v4/projects/{owner}%2F{repo}/releases")
+			}
+		}
+	}
+}
+
+/// Configuration for talking to a forge's REST API.
+#[derive(Debug, Clone)]
+pub struct PublishConfig {
+	/// Forge to publish to.
+	pub forge:    ForgeKind,
+	/// API base URL, e.g. `https://gitea.example.com`.
+	pub base_url: String,
+	/// Authentication token, usually read from [`PUBLISH_TOKEN_ENV`].
+	pub token:    String,
+	/// Repository owner/organization.
+	pub owner:    String,
+	/// Repository name.
+	pub repo:     String,
+}
+
+impl PublishConfig {
+	/// Builds a config reading the API token from [`PUBLISH_TOKEN_ENV`].
+	pub fn from_env(
+		forge: ForgeKind,
+		base_url: String,
+		owner: String,
+		repo: String,
+	) -> Result<Self> {
+		let token = std::env::var(PUBLISH_TOKEN_ENV).map_err(|_| {
+			crate::error::Error::ConfigError(format!(
+				"environment variable {PUBLISH_TOKEN_ENV} is not set"
+			))
+		})?;
+		Ok(Self {
+			forge,
+			base_url,
+			token,
+			owner,
+			repo,
+		})
+	}
+}
+
+/// Payload sent to a forge to create a release.
+///
+/// This is the wire format every supported forge expects; fields that a
+/// forge does not understand are ignored on its end.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRelease {
+	/// Tag that the release points to.
+	pub tag_name:         String,
+	/// Commit (or branch) the tag is created from, if the tag does not
+	/// exist yet.
+	pub target_commitish: Option<String>,
+	/// Human-readable release title.
+	pub name:             Option<String>,
+	/// Release notes, usually the rendered changelog for that release.
+	pub body:             Option<String>,
+	/// Whether the release should be created as a draft.
+	pub draft:            bool,
+	/// Whether the release should be marked as a prerelease.
+	pub prerelease:       bool,
+	/// URLs or paths of assets to attach to the release.
+	pub assets:           Vec<String>,
+}
+
+impl<'a> From<&Release<'a>> for CreateRelease {
+	fn from(release: &Release<'a>) -> Self {
+		Self {
+			tag_name:         release.version.clone().unwrap_or_default(),
+			target_commitish: release.commit_id.clone(),
+			name:             release.name.clone(),
+			body:             release.body.clone(),
+			draft:            release.draft.unwrap_or(false),
+			prerelease:       release.prerelease.unwrap_or(false),
+			assets:           release.assets.clone(),
+		}
+	}
+}
+
+/// Release as reported back by the forge after creation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreatedRelease {
+	/// Forge-assigned release id. GitLab releases are keyed by tag rather
+	/// than id, so this is `None` for GitLab.
+	pub id:  Option<u64>,
+	/// URL that can be used to view the release in a browser.
+	pub url: String,
+}
+
+/// Response shape of Gitea's and GitHub's "create a release" endpoint.
+#[derive(Debug, Deserialize)]
+struct IdKeyedResponse {
+	id:       u64,
+	html_url: String,
+}
+
+/// Response shape of GitLab's "create a release" endpoint, which has no
+/// numeric id -- releases are keyed by `tag_name`.
+#[derive(Debug, Deserialize)]
+struct GitLabResponse {
+	#[serde(rename = "_links")]
+	links: GitLabLinks,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabLinks {
+	#[serde(rename = "self")]
+	self_url: String,
+}
+
+/// Publishes releases to a forge's REST API.
+///
+/// Uses an async `reqwest::Client` rather than `reqwest::blocking` so this
+/// can be called from within the tokio runtime that the crate's existing
+/// `remote` (PR/contributor enrichment) integration already drives.
+pub struct PublishClient {
+	config: PublishConfig,
+	client: reqwest::Client,
+}
+
+impl PublishClient {
+	/// Constructs a client for the given configuration.
+	pub fn new(config: PublishConfig) -> Self {
+		Self {
+			config,
+			client: reqwest::Client::new(),
+		}
+	}
+
+	/// Creates a release on the configured forge and returns its id/url.
+	pub async fn publish(&self, release: &Release<'_>) -> Result<CreatedRelease> {
+		let payload = CreateRelease::from(release);
+		let path = self
+			.config
+			.forge
+			.releases_path(&self.config.owner, &self.config.repo);
+		let url = format!("{}{}", self.config.base_url.trim_end_matches('/'), path);
+		let response = self
+			.client
+			.post(url)
+			.bearer_auth(&self.config.token)
+			.json(&payload)
+			.send()
+			.await?;
+		let response = response.error_for_status()?;
+		match self.config.forge {
+			ForgeKind::Gitea | ForgeKind::GitHub => {
+				let body: IdKeyedResponse = response.json().await?;
+				Ok(CreatedRelease {
+					id:  Some(body.id),
+					url: body.html_url,
+				})
+			}
+			ForgeKind::GitLab => {
+				let body: GitLabResponse = response.json().await?;
+				Ok(CreatedRelease {
+					id:  None,
+					url: body.links.self_url,
+				})
+			}
+		}
+	}
+}
+
+/// Builds a client from the environment and publishes `release` to it.
+///
+/// This is the single entry point the `git-cliff` binary's `--publish` flag
+/// is expected to call.
+pub async fn publish_release_from_env(
+	release: &Release<'_>,
+	forge: ForgeKind,
+	base_url: String,
+	owner: String,
+	repo: String,
+) -> Result<CreatedRelease> {
+	let config = PublishConfig::from_env(forge, base_url, owner, repo)?;
+	PublishClient::new(config).publish(release).await
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn releases_path_per_forge() {
+		assert_eq!(
+			"/api/v1/repos/o/r/releases",
+			ForgeKind::Gitea.releases_path("o", "r")
+		);
+		assert_eq!(
+			"/repos/o/r/releases",
+			ForgeKind::GitHub.releases_path("o", "r")
+		);
+		assert_eq!(
+			"/api/v4/projects/o%2Fr/releases",
+			ForgeKind::GitLab.releases_path("o", "r")
+		);
+	}
+
+	#[test]
+	fn create_release_from_release() {
+		let release = Release {
+			version: Some(String::from("v1.0.0")),
+			commit_id: Some(String::from("abcdef")),
+			name: Some(String::from("v1.0.0")),
+			body: Some(String::from("- first release")),
+			draft: Some(true),
+			prerelease: Some(false),
+			assets: vec![String::from("dist/app.tar.gz")],
+			..Default::default()
+		};
+		let payload = CreateRelease::from(&release);
+		assert_eq!("v1.0.0", payload.tag_name);
+		assert_eq!(Some(String::from("abcdef")), payload.target_commitish);
+		assert!(payload.draft);
+		assert!(!payload.prerelease);
+		assert_eq!(vec![String::from("dist/app.tar.gz")], payload.assets);
+	}
+
+	#[test]
+	fn from_env_reads_token() {
+		std::env::set_var(PUBLISH_TOKEN_ENV, "test-token");
+		let config = PublishConfig::from_env(
+			ForgeKind::Gitea,
+			String::from("https://gitea.example.com"),
+			String::from("o"),
+			String::from("r"),
+		)
+		.expect("token was set");
+		assert_eq!("test-token", config.token);
+		std::env::remove_var(PUBLISH_TOKEN_ENV);
+	}
+}
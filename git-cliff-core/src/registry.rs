@@ -0,0 +1,140 @@
+use crate::error::{
+	Error,
+	Result,
+};
+use semver::Version;
+use serde::Deserialize;
+
+/// Base URL of the crates.io API.
+const CRATES_IO_API_URL: &str = "https://crates.io/api/v1/crates";
+
+/// Config option gating the crates.io fallback used by
+/// [`crate::release::Release::calculate_next_version_with_registry`].
+///
+/// Disabled by default: the lookup only runs when a config/CLI layer opts
+/// in with a crate name, since it requires network access.
+#[derive(Debug, Clone)]
+pub struct RegistryFallback {
+	/// Name of the crate to look up on crates.io.
+	pub crate_name: String,
+	/// Whether the fallback is enabled.
+	pub enabled:    bool,
+}
+
+impl RegistryFallback {
+	/// Enables the fallback for `crate_name`.
+	pub fn enabled(crate_name: impl Into<String>) -> Self {
+		Self {
+			crate_name: crate_name.into(),
+			enabled:    true,
+		}
+	}
+}
+
+/// A single version entry as returned by the crates.io API.
+#[derive(Debug, Deserialize)]
+struct CrateVersion {
+	num:    String,
+	/// Whether this version has been yanked; yanked versions must not be
+	/// used as a bump baseline.
+	yanked: bool,
+}
+
+/// Response shape of `GET /api/v1/crates/{name}`.
+#[derive(Debug, Deserialize)]
+struct CrateResponse {
+	versions: Vec<CrateVersion>,
+}
+
+/// Picks the highest non-yanked version out of a crates.io response,
+/// erroring if every version is yanked (or there are none).
+fn highest_unyanked(name: &str, body: CrateResponse) -> Result<Version> {
+	body.versions
+		.iter()
+		.filter(|version| !version.yanked)
+		.filter_map(|version| Version::parse(&version.num).ok())
+		.max()
+		.ok_or_else(|| Error::RegistryError(format!("{name} has no published versions")))
+}
+
+/// Queries crates.io for the highest non-yanked version published for
+/// `name`.
+///
+/// This is used to seed
+/// [`crate::release::Release::calculate_next_version_with_registry`] (and
+/// [`crate::release::Release::calculate_next_version_with_options`]) when
+/// no git tag exists for the previous release, e.g. right after a
+/// crate has been published without tagging the commit.
+///
+/// Uses `reqwest::blocking`, so it must not be called directly from a thread
+/// driven by a tokio runtime (e.g. the one [`crate::publish::PublishClient`]
+/// runs on) -- doing so panics with "Cannot drop a runtime in a context
+/// where blocking is not allowed". Call [`latest_published_version_async`]
+/// instead from async code.
+pub fn latest_published_version(name: &str) -> Result<Version> {
+	let url = format!("{CRATES_IO_API_URL}/{name}");
+	let response = reqwest::blocking::Client::new()
+		.get(url)
+		.header(
+			reqwest::header::USER_AGENT,
+			"git-cliff (https://github.com/orhun/git-cliff)",
+		)
+		.send()?;
+	match response.status() {
+		reqwest::StatusCode::OK => {
+			let body: CrateResponse = response.json()?;
+			highest_unyanked(name, body)
+		}
+		reqwest::StatusCode::NOT_FOUND => Err(Error::RegistryError(format!(
+			"crate not found on crates.io: {name}"
+		))),
+		status => Err(Error::RegistryError(format!(
+			"unexpected response from crates.io for {name}: {status}"
+		))),
+	}
+}
+
+/// Async-safe equivalent of [`latest_published_version`].
+///
+/// Runs the blocking lookup on tokio's blocking thread pool via
+/// [`tokio::task::spawn_blocking`], so this is the version to call from
+/// async code such as [`crate::publish::PublishClient`]'s call sites.
+pub async fn latest_published_version_async(name: &str) -> Result<Version> {
+	let name = name.to_owned();
+	tokio::task::spawn_blocking(move || latest_published_version(&name))
+		.await
+		.map_err(|error| Error::RegistryError(format!("registry lookup task panicked: {error}")))?
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn parses_highest_version() -> Result<()> {
+		let response: CrateResponse = serde_json::from_str(
+			r#"{"versions":[{"num":"1.2.0","yanked":false},{"num":"1.10.0","yanked":false},{"num":"1.3.0","yanked":false}]}"#,
+		)
+		.expect("valid response fixture");
+		assert_eq!(Version::new(1, 10, 0), highest_unyanked("foo", response)?);
+		Ok(())
+	}
+
+	#[test]
+	fn ignores_yanked_versions() -> Result<()> {
+		let response: CrateResponse = serde_json::from_str(
+			r#"{"versions":[{"num":"1.2.0","yanked":false},{"num":"1.10.0","yanked":true}]}"#,
+		)
+		.expect("valid response fixture");
+		assert_eq!(Version::new(1, 2, 0), highest_unyanked("foo", response)?);
+		Ok(())
+	}
+
+	#[test]
+	fn errors_when_every_version_is_yanked() {
+		let response: CrateResponse =
+			serde_json::from_str(r#"{"versions":[{"num":"1.2.0","yanked":true}]}"#)
+				.expect("valid response fixture");
+		assert!(highest_unyanked("foo", response).is_err());
+	}
+}
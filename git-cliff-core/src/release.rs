@@ -1,5 +1,7 @@
+use crate::cache;
 use crate::commit::Commit;
 use crate::error::Result;
+use crate::registry;
 use next_version::NextVersion;
 use semver::Version;
 use serde::{
@@ -7,41 +9,115 @@ use serde::{
 	Serialize,
 };
 
+/// Strategy for computing the next version in
+/// [`Release::calculate_next_version_with_options`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BumpMode {
+	/// Standard `MAJOR.MINOR.PATCH` bump, e.g. `1.2.3` -> `1.3.0`.
+	Normal,
+	/// Bump or increment a prerelease identifier, e.g. `1.3.0-rc.1` ->
+	/// `1.3.0-rc.2`.
+	Prerelease {
+		/// Prerelease channel, e.g. `rc` or `alpha`.
+		channel: String,
+	},
+	/// Attach build metadata (`+<shorthash>` or `+<timestamp>`). Build
+	/// metadata is always additive and never affects precedence.
+	BuildMetadata {
+		/// Use the release's commit id instead of its timestamp.
+		use_commit_id: bool,
+	},
+}
+
+impl Default for BumpMode {
+	fn default() -> Self {
+		BumpMode::Normal
+	}
+}
+
 /// Representation of a release.
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Release<'a> {
 	/// Release version, git tag.
-	pub version:   Option<String>,
+	pub version:    Option<String>,
 	/// Commits made for the release.
-	pub commits:   Vec<Commit<'a>>,
+	pub commits:    Vec<Commit<'a>>,
 	/// Commit ID of the tag.
 	#[serde(rename = "commit_id")]
-	pub commit_id: Option<String>,
+	pub commit_id:  Option<String>,
 	/// Timestamp of the release in seconds, from epoch.
-	pub timestamp: i64,
+	pub timestamp:  i64,
 	/// Previous release.
-	pub previous:  Option<Box<Release<'a>>>,
+	pub previous:   Option<Box<Release<'a>>>,
+	/// Human-readable release title, distinct from `version`.
+	///
+	/// Deliberately has no `skip_serializing_if`: this struct is also
+	/// serialized with bincode for [`cache`] (a non-self-describing format),
+	/// where skipping a field desyncs the byte stream instead of omitting
+	/// it. [`Releases::as_json`] strips unset forge metadata separately.
+	pub name:       Option<String>,
+	/// Rendered release notes, usually the generated changelog entry for
+	/// this release.
+	pub body:       Option<String>,
+	/// Whether the release should be created as a draft.
+	pub draft:      Option<bool>,
+	/// Whether the release should be marked as a prerelease.
+	pub prerelease: Option<bool>,
+	/// URLs or paths of assets to attach to the release.
+	pub assets:     Vec<String>,
 }
 
 impl<'a> Release<'a> {
 	/// Calculates the next version based on the commits.
 	pub fn calculate_next_version(&self) -> Result<String> {
-		match self
+		self.calculate_next_version_with_options(None, &BumpMode::Normal)
+	}
+
+	/// Same as [`Self::calculate_next_version`], but additionally resolves the
+	/// previous version from crates.io when no git-tagged previous release
+	/// exists. Pass an enabled [`registry::RegistryFallback`] to turn the
+	/// lookup on; `None` (or a disabled fallback) preserves the original
+	/// tag-only behavior.
+	pub fn calculate_next_version_with_registry(
+		&self,
+		registry_fallback: Option<&registry::RegistryFallback>,
+	) -> Result<String> {
+		self.calculate_next_version_with_options(registry_fallback, &BumpMode::Normal)
+	}
+
+	/// Calculates the next version, resolving the previous version as
+	/// [`Self::calculate_next_version_with_registry`] does, and shaping the
+	/// result according to `mode` (see [`BumpMode`]).
+	pub fn calculate_next_version_with_options(
+		&self,
+		registry_fallback: Option<&registry::RegistryFallback>,
+		mode: &BumpMode,
+	) -> Result<String> {
+		let previous_version = match self
 			.previous
 			.as_ref()
 			.and_then(|release| release.version.clone())
 		{
+			Some(version) => Some(version),
+			None => registry_fallback
+				.filter(|fallback| fallback.enabled)
+				.and_then(|fallback| {
+					match registry::latest_published_version(&fallback.crate_name) {
+						Ok(version) => Some(version.to_string()),
+						Err(e) => {
+							warn!(
+								"Failed to resolve previous version from crates.io: {e}"
+							);
+							None
+						}
+					}
+				}),
+		};
+		match previous_version {
 			Some(version) => {
-				let next_version = Version::parse(version.trim_start_matches('v'))?
-					.next(
-						self.commits
-							.iter()
-							.map(|commit| commit.message.trim_end().to_string())
-							.collect::<Vec<String>>(),
-					)
-					.to_string();
-				Ok(next_version)
+				let previous = Version::parse(version.trim_start_matches('v'))?;
+				Ok(self.bump_version(&previous, mode)?.to_string())
 			}
 			None => {
 				warn!("No releases found, using 0.0.1 as the next version.");
@@ -49,6 +125,97 @@ impl<'a> Release<'a> {
 			}
 		}
 	}
+
+	/// Bumps `previous` according to `mode`, using the commit messages of
+	/// this release to determine the semantic increment (breaking -> major,
+	/// feat -> minor, fix -> patch).
+	fn bump_version(&self, previous: &Version, mode: &BumpMode) -> Result<Version> {
+		let commit_messages = || {
+			self.commits
+				.iter()
+				.map(|commit| commit.message.trim_end().to_string())
+				.collect::<Vec<String>>()
+		};
+		match mode {
+			BumpMode::Normal => Ok(previous.clone().next(commit_messages())),
+			BumpMode::Prerelease { channel } => {
+				match Self::same_channel_counter(previous, channel) {
+					Some(counter) => {
+						let mut next = previous.clone();
+						next.pre = semver::Prerelease::new(&format!(
+							"{channel}.{}",
+							counter + 1
+						))?;
+						Ok(next)
+					}
+					None => {
+						let mut next = previous.clone().next(commit_messages());
+						next.pre = semver::Prerelease::new(&format!("{channel}.0"))?;
+						Ok(next)
+					}
+				}
+			}
+			BumpMode::BuildMetadata { use_commit_id } => {
+				let mut next = previous.clone().next(commit_messages());
+				let metadata = if *use_commit_id {
+					self.commit_id
+						.as_deref()
+						.map(|id| id.chars().take(7).collect::<String>())
+						.unwrap_or_default()
+				} else {
+					self.timestamp.to_string()
+				};
+				next.build = semver::BuildMetadata::new(&metadata)?;
+				Ok(next)
+			}
+		}
+	}
+
+	/// Returns the numeric tail of `previous`'s prerelease identifier if it
+	/// belongs to `channel`, treating an empty or non-numeric tail as `0`.
+	/// Returns `None` if `previous` has no prerelease identifier, or one
+	/// belonging to a different channel.
+	fn same_channel_counter(previous: &Version, channel: &str) -> Option<u64> {
+		if previous.pre.is_empty() {
+			return None;
+		}
+		let mut parts = previous.pre.as_str().splitn(2, '.');
+		if parts.next()? != channel {
+			return None;
+		}
+		Some(parts.next().and_then(|tail| tail.parse().ok()).unwrap_or(0))
+	}
+
+	/// Returns an owned copy of this release, detached from the lifetime of
+	/// any borrowed commit data, suitable for caching to disk.
+	fn into_owned(&self) -> Release<'static> {
+		serde_json::from_str(&serde_json::to_string(self).expect("release is serializable"))
+			.expect("release round-trips through its own serde impl")
+	}
+}
+
+/// Removes `name`/`body`/`draft`/`prerelease` when `null` and `assets` when
+/// empty from a JSON-encoded [`Release`], recursing into `previous` since
+/// each release in the chain carries the same forge metadata fields.
+fn strip_unset_forge_metadata(release: &mut serde_json::Value) {
+	let Some(map) = release.as_object_mut() else {
+		return;
+	};
+	for key in ["name", "body", "draft", "prerelease"] {
+		if map.get(key).is_some_and(serde_json::Value::is_null) {
+			map.remove(key);
+		}
+	}
+	if map
+		.get("assets")
+		.and_then(serde_json::Value::as_array)
+		.is_some_and(Vec::is_empty)
+	{
+		map.remove("assets");
+	}
+	if let Some(previous) = map.get_mut("previous") {
+		strip_unset_forge_metadata(previous);
+	}
 }
 
 /// Representation of a list of releases.
@@ -60,8 +227,62 @@ pub struct Releases<'a> {
 
 impl<'a> Releases<'a> {
 	/// Returns the list of releases as JSON.
+	///
+	/// Unset forge metadata (`name`/`body`/`draft`/`prerelease`/`assets`) is
+	/// stripped from the output so existing consumers don't see a schema
+	/// change for ordinary releases; see the doc comment on [`Release::name`].
 	pub fn as_json(&self) -> Result<String> {
-		Ok(serde_json::to_string(self.releases)?)
+		let mut value = serde_json::to_value(self.releases)?;
+		if let serde_json::Value::Array(releases) = &mut value {
+			for release in releases {
+				strip_unset_forge_metadata(release);
+			}
+		}
+		Ok(serde_json::to_string(&value)?)
+	}
+
+	/// Serializes the releases to `path` as a binary cache, keyed by `head`
+	/// (the current HEAD commit id).
+	pub fn save_cache(&self, path: &std::path::Path, head: &str) -> Result<()> {
+		let cache = cache::CacheFile {
+			head:     head.to_string(),
+			releases: self
+				.releases
+				.iter()
+				.map(Release::into_owned)
+				.collect(),
+		};
+		std::fs::write(path, bincode::serialize(&cache)?)?;
+		Ok(())
+	}
+
+	/// Loads a previously cached release graph from `path`.
+	///
+	/// Returns `Ok(None)` when there is no cache file at `path`, or when the
+	/// cached HEAD does not match `head` -- i.e. the cache is stale and must
+	/// be invalidated by recomputing the releases.
+	pub fn load_cache(
+		path: &std::path::Path,
+		head: &str,
+	) -> Result<Option<Vec<Release<'static>>>> {
+		match Self::load_cache_file(path)? {
+			Some(cache) if cache.head == head => Ok(Some(cache.releases)),
+			_ => Ok(None),
+		}
+	}
+
+	/// Loads the raw cache file at `path`, regardless of the HEAD it was
+	/// generated at.
+	///
+	/// Returns `Ok(None)` when there is no cache file at `path`. Used by
+	/// [`cache::load_stale`] to hand a stale cache entry back to a caller
+	/// that wants to incrementally update it instead of recomputing
+	/// everything.
+	pub(crate) fn load_cache_file(path: &std::path::Path) -> Result<Option<cache::CacheFile>> {
+		if !path.exists() {
+			return Ok(None);
+		}
+		Ok(Some(bincode::deserialize(&std::fs::read(path)?)?))
 	}
 }
 
@@ -103,4 +324,149 @@ mod test {
 		assert_eq!("0.0.1", next_version);
 		Ok(())
 	}
+
+	#[test]
+	fn bump_version_prerelease() -> Result<()> {
+		for (expected_version, previous_version, channel, commits) in [
+			("1.1.0-rc.0", "1.0.0", "rc", vec!["feat: add xyz"]),
+			("1.0.0-rc.1", "1.0.0-rc.0", "rc", vec!["fix: fix xyz"]),
+			("1.0.0-rc.1", "1.0.0-rc", "rc", vec!["fix: fix xyz"]),
+			// Previous prerelease is on a different channel ("rc"), so the
+			// "beta" channel requested here starts fresh at `.0` on top of
+			// a regular core bump rather than incrementing `rc`.
+			("1.0.1-beta.0", "1.0.0-rc.3", "beta", vec!["fix: fix xyz"]),
+		] {
+			let release = Release {
+				commits: commits
+					.into_iter()
+					.map(|v| Commit::from(v.to_string()))
+					.collect(),
+				previous: Some(Box::new(Release {
+					version: Some(String::from(previous_version)),
+					..Default::default()
+				})),
+				..Default::default()
+			};
+			let mode = BumpMode::Prerelease {
+				channel: String::from(channel),
+			};
+			let next_version = release.calculate_next_version_with_options(None, &mode)?;
+			assert_eq!(expected_version, next_version);
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn bump_version_build_metadata() -> Result<()> {
+		let release = Release {
+			commits:   vec![Commit::from(String::from("fix: fix xyz"))],
+			commit_id: Some(String::from("abcdef1234567")),
+			timestamp: 42,
+			previous:  Some(Box::new(Release {
+				version: Some(String::from("1.0.0")),
+				..Default::default()
+			})),
+			..Default::default()
+		};
+		let next_version = release.calculate_next_version_with_options(
+			None,
+			&BumpMode::BuildMetadata { use_commit_id: true },
+		)?;
+		assert_eq!("1.0.1+abcdef1", next_version);
+		let next_version = release.calculate_next_version_with_options(
+			None,
+			&BumpMode::BuildMetadata { use_commit_id: false },
+		)?;
+		assert_eq!("1.0.1+42", next_version);
+		Ok(())
+	}
+
+	#[test]
+	fn cache_round_trips_matching_head() -> Result<()> {
+		let path = std::env::temp_dir().join("git-cliff-release-cache-test-match.cache");
+		let releases = vec![Release {
+			version: Some(String::from("1.0.0")),
+			..Default::default()
+		}];
+		let releases_ref = Releases {
+			releases: &releases,
+		};
+		releases_ref.save_cache(&path, "deadbeef")?;
+		let loaded = Releases::load_cache(&path, "deadbeef")?;
+		assert_eq!(Some(releases), loaded);
+		std::fs::remove_file(&path)?;
+		Ok(())
+	}
+
+	#[test]
+	fn cache_round_trips_with_forge_metadata_set() -> Result<()> {
+		let path =
+			std::env::temp_dir().join("git-cliff-release-cache-test-forge-metadata.cache");
+		let releases = vec![Release {
+			version: Some(String::from("1.0.0")),
+			name: Some(String::from("v1.0.0")),
+			body: Some(String::from("- first release")),
+			draft: Some(true),
+			prerelease: Some(false),
+			assets: vec![String::from("dist/app.tar.gz")],
+			..Default::default()
+		}];
+		let releases_ref = Releases {
+			releases: &releases,
+		};
+		releases_ref.save_cache(&path, "deadbeef")?;
+		let loaded = Releases::load_cache(&path, "deadbeef")?;
+		assert_eq!(Some(releases), loaded);
+		std::fs::remove_file(&path)?;
+		Ok(())
+	}
+
+	#[test]
+	fn cache_invalidates_on_head_mismatch() -> Result<()> {
+		let path = std::env::temp_dir().join("git-cliff-release-cache-test-mismatch.cache");
+		let releases = Vec::new();
+		let releases_ref = Releases {
+			releases: &releases,
+		};
+		releases_ref.save_cache(&path, "deadbeef")?;
+		let loaded = Releases::load_cache(&path, "f00dcafe")?;
+		assert_eq!(None, loaded);
+		std::fs::remove_file(&path)?;
+		Ok(())
+	}
+
+	#[test]
+	fn forge_metadata_omitted_from_json_when_unset() -> Result<()> {
+		let release = Release {
+			version: Some(String::from("1.0.0")),
+			..Default::default()
+		};
+		let releases = vec![release];
+		let json = Releases {
+			releases: &releases,
+		}
+		.as_json()?;
+		for key in ["name", "body", "draft", "prerelease", "assets"] {
+			assert!(!json.contains(key), "unexpected `{key}` in {json}");
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn forge_metadata_present_in_json_when_set() -> Result<()> {
+		let release = Release {
+			version: Some(String::from("1.0.0")),
+			name: Some(String::from("v1.0.0")),
+			assets: vec![String::from("dist/app.tar.gz")],
+			..Default::default()
+		};
+		let releases = vec![release];
+		let json = Releases {
+			releases: &releases,
+		}
+		.as_json()?;
+		assert!(json.contains("\"name\":\"v1.0.0\""));
+		assert!(json.contains("\"assets\":[\"dist/app.tar.gz\"]"));
+		Ok(())
+	}
 }